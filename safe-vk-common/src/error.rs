@@ -0,0 +1,31 @@
+use std::fmt;
+
+/// The error type returned anywhere in `safe-vk`, from a failed HTTP call
+/// down to a handler whose extractors couldn't be built from the update.
+#[derive(Debug)]
+pub enum Error {
+    /// The underlying HTTP request to the VK API failed.
+    Http(String),
+    /// The VK API's JSON response could not be parsed into the expected shape.
+    Json(String),
+    /// VK answered with an API-level error (`error_code` / `error_msg`).
+    Vk { code: i64, message: String },
+    /// An extractor could not build its value from the incoming update.
+    Extraction(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(msg) => write!(f, "HTTP request failed: {msg}"),
+            Error::Json(msg) => write!(f, "failed to parse VK response: {msg}"),
+            Error::Vk { code, message } => write!(f, "VK API error {code}: {message}"),
+            Error::Extraction(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// A convenience alias for `Result<T, Error>`, used throughout the crate.
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,26 @@
+/// Controls how strictly an incoming message's text must match a
+/// registered command keyword before its handler runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    /// The message text must equal the command exactly.
+    Strict,
+    /// The command must be the message's first whitespace-separated token;
+    /// anything after it is left for the handler (or an extractor) to parse.
+    Prefix,
+    /// The command may appear anywhere in the message text.
+    Contains,
+}
+
+impl Filter {
+    /// Returns `true` if `text` satisfies this filter for `command`.
+    pub fn matches(&self, command: &str, text: &str) -> bool {
+        match self {
+            Filter::Strict => text == command,
+            Filter::Prefix => text
+                .split_whitespace()
+                .next()
+                .is_some_and(|first| first == command),
+            Filter::Contains => text.contains(command),
+        }
+    }
+}
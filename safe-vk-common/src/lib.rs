@@ -0,0 +1,11 @@
+//! Types shared by `safe-vk` and `safe-vk-macros`.
+//!
+//! This crate exists so the macro crate can refer to [`Error`] and [`Filter`]
+//! without depending on `safe-vk` itself (which in turn depends on the
+//! macro crate), avoiding a dependency cycle.
+
+mod error;
+mod filter;
+
+pub use error::{Error, Result};
+pub use filter::Filter;
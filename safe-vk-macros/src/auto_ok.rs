@@ -0,0 +1,13 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, parse_quote, ItemFn};
+
+pub(crate) fn expand(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut func = parse_macro_input!(input as ItemFn);
+    let block = func.block;
+    func.block = parse_quote!({
+        #block
+        Ok(())
+    });
+    quote!(#func).into()
+}
@@ -0,0 +1,180 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{
+    parse_macro_input, punctuated::Punctuated, Data, DeriveInput, Expr, ExprLit, Fields,
+    GenericArgument, Lit, MetaNameValue, PathArguments, Token, Type,
+};
+
+enum FieldKind {
+    Flag(String),
+    Option(Type),
+    Vec(Type),
+    Plain(Type),
+}
+
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let ident = &input.ident;
+
+    let command = match string_attr(&input.attrs, "command", "name") {
+        Some(name) => name,
+        None => {
+            return syn::Error::new_spanned(
+                ident,
+                "#[derive(Command)] requires #[command(name = \"/keyword\")]",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+    let explicit_usage = string_attr(&input.attrs, "command", "usage");
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    ident,
+                    "#[derive(Command)] only supports structs with named fields",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(ident, "#[derive(Command)] only supports structs")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut usage_parts = Vec::new();
+    let mut flag_binds = Vec::new();
+    let mut positional_binds = Vec::new();
+    let mut field_names = Vec::new();
+
+    for field in fields {
+        let name = field.ident.clone().expect("named field");
+        field_names.push(name.clone());
+        let kind = classify_field(field, &name);
+
+        match kind {
+            FieldKind::Flag(flag) => {
+                usage_parts.push(format!("[{flag} <value>]"));
+                flag_binds.push(quote! {
+                    let #name = safe_vk::extract::take_flag(&mut tokens, #flag)
+                        .map(|value| value.parse().map_err(|_| safe_vk::Error::Extraction(usage.to_owned())))
+                        .transpose()?;
+                });
+            }
+            FieldKind::Option(inner) => {
+                usage_parts.push(format!("[<{name}>]"));
+                positional_binds.push(quote! {
+                    let #name: Option<#inner> = if tokens.is_empty() {
+                        None
+                    } else {
+                        Some(tokens.remove(0).parse().map_err(|_| safe_vk::Error::Extraction(usage.to_owned()))?)
+                    };
+                });
+            }
+            FieldKind::Vec(inner) => {
+                usage_parts.push(format!("[<{name}>...]"));
+                positional_binds.push(quote! {
+                    let #name: Vec<#inner> = tokens
+                        .drain(..)
+                        .map(|token| token.parse().map_err(|_| safe_vk::Error::Extraction(usage.to_owned())))
+                        .collect::<std::result::Result<_, _>>()?;
+                });
+            }
+            FieldKind::Plain(ty) => {
+                usage_parts.push(format!("<{name}>"));
+                positional_binds.push(quote! {
+                    if tokens.is_empty() {
+                        return Err(safe_vk::Error::Extraction(usage.to_owned()));
+                    }
+                    let #name: #ty = tokens.remove(0).parse().map_err(|_| safe_vk::Error::Extraction(usage.to_owned()))?;
+                });
+            }
+        }
+    }
+
+    let usage = explicit_usage
+        .unwrap_or_else(|| format!("Usage: {command} {}", usage_parts.join(" ")).trim_end().to_owned());
+
+    let expanded = quote! {
+        impl<S> safe_vk::extract::FromUpdate<S> for #ident {
+            fn from_update(
+                update: &safe_vk::responses::Message,
+                _vk: &safe_vk::VK,
+                _state: &S,
+            ) -> std::result::Result<Self, safe_vk::Error> {
+                let usage = <Self as safe_vk::extract::TypedCommand>::usage();
+                let rest = update.text
+                    .strip_prefix(<Self as safe_vk::extract::TypedCommand>::COMMAND)
+                    .ok_or_else(|| safe_vk::Error::Extraction(usage.to_owned()))?;
+                let mut tokens = safe_vk::extract::tokenize(rest);
+
+                #(#flag_binds)*
+                #(#positional_binds)*
+
+                Ok(Self { #(#field_names),* })
+            }
+        }
+
+        impl safe_vk::extract::TypedCommand for #ident {
+            const COMMAND: &'static str = #command;
+
+            fn usage() -> &'static str {
+                #usage
+            }
+        }
+    };
+
+    expanded.into()
+}
+
+fn classify_field(field: &syn::Field, name: &syn::Ident) -> FieldKind {
+    if let Some(flag) = string_attr(&field.attrs, "arg", "flag") {
+        return FieldKind::Flag(flag);
+    }
+    let _ = name;
+    classify_type(&field.ty)
+}
+
+fn classify_type(ty: &Type) -> FieldKind {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                    if segment.ident == "Option" {
+                        return FieldKind::Option(inner.clone());
+                    }
+                    if segment.ident == "Vec" {
+                        return FieldKind::Vec(inner.clone());
+                    }
+                }
+            }
+        }
+    }
+    FieldKind::Plain(ty.clone())
+}
+
+/// Reads `value` out of a `#[outer(key = "value")]` attribute, if present.
+fn string_attr(attrs: &[syn::Attribute], outer: &str, key: &str) -> Option<String> {
+    for attr in attrs {
+        if !attr.path().is_ident(outer) {
+            continue;
+        }
+        let pairs = attr
+            .parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)
+            .ok()?;
+        for pair in pairs {
+            if pair.path.is_ident(key) {
+                if let Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) = pair.value {
+                    return Some(s.value());
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,37 @@
+//! Procedural macros for [`safe-vk`](https://docs.rs/safe-vk).
+
+use proc_macro::TokenStream;
+
+mod auto_ok;
+mod command;
+
+/// Wraps an `async fn` handler body in `Ok(())` so a handler can end in a
+/// bare `?` instead of an explicit final `Ok(())`; see the `safe-vk`
+/// crate docs for an example.
+#[proc_macro_attribute]
+pub fn auto_ok(args: TokenStream, input: TokenStream) -> TokenStream {
+    auto_ok::expand(args, input)
+}
+
+/// Derives `extract::FromUpdate` and `extract::TypedCommand` for a struct,
+/// parsing it out of an incoming message's text.
+///
+/// ```ignore
+/// #[derive(Command)]
+/// #[command(name = "/weather")]
+/// struct Weather {
+///     city: String,
+///     #[arg(flag = "--units")]
+///     units: Option<String>,
+/// }
+/// ```
+///
+/// Positional fields are filled in declaration order via their `FromStr`
+/// impl. A field annotated `#[arg(flag = "--name")]` is instead pulled out
+/// of the message as a named option wherever it appears. A plain
+/// `Option<T>` field is optional; a trailing `Vec<T>` field greedily
+/// collects whatever positional tokens remain.
+#[proc_macro_derive(Command, attributes(command, arg))]
+pub fn derive_command(input: TokenStream) -> TokenStream {
+    command::expand(input)
+}
@@ -0,0 +1,104 @@
+use std::{future::IntoFuture, path::Path};
+
+use super::upload;
+use crate::{handler::BoxFuture, reqwest_ext::VK, Result};
+
+/// Entry point for `messages.*` API methods, scoped to the peer the
+/// triggering update came from unless overridden with
+/// [`SendBuilder::peer_id`].
+pub struct MessagesApi {
+    vk: VK,
+    default_peer_id: i64,
+}
+
+impl MessagesApi {
+    pub(crate) fn new(vk: VK, default_peer_id: i64) -> Self {
+        Self { vk, default_peer_id }
+    }
+
+    /// Starts building a `messages.send` call.
+    pub fn send(&self) -> SendBuilder {
+        SendBuilder {
+            vk: self.vk.clone(),
+            peer_id: self.default_peer_id,
+            random_id: 0,
+            message: None,
+            attachments: Vec::new(),
+        }
+    }
+}
+
+/// Builds a `messages.send` call. Awaiting the builder sends it.
+pub struct SendBuilder {
+    vk: VK,
+    peer_id: i64,
+    random_id: i64,
+    message: Option<String>,
+    attachments: Vec<String>,
+}
+
+impl SendBuilder {
+    /// Overrides the peer the message is sent to.
+    pub fn peer_id(mut self, peer_id: i64) -> Self {
+        self.peer_id = peer_id;
+        self
+    }
+
+    /// Sets VK's de-duplication id for this send.
+    pub fn random_id(mut self, random_id: i64) -> Self {
+        self.random_id = random_id;
+        self
+    }
+
+    /// Sets the message text.
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Uploads `path` as a photo via `photos.getMessagesUploadServer` /
+    /// `photos.saveMessagesPhoto` and attaches the result.
+    pub async fn attach_photo(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let attachment = upload::photo(&self.vk, self.peer_id, path.as_ref()).await?;
+        self.attachments.push(attachment.to_string());
+        Ok(self)
+    }
+
+    /// Same as [`attach_photo`](Self::attach_photo), for an arbitrary
+    /// document via the `docs.*` upload endpoints.
+    pub async fn attach_document(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let attachment = upload::document(&self.vk, self.peer_id, path.as_ref()).await?;
+        self.attachments.push(attachment.to_string());
+        Ok(self)
+    }
+
+    /// Same as [`attach_photo`](Self::attach_photo), uploading `path` as a
+    /// voice message (`docs.*` with `type = "audio_message"`).
+    pub async fn attach_voice(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let attachment = upload::voice(&self.vk, self.peer_id, path.as_ref()).await?;
+        self.attachments.push(attachment.to_string());
+        Ok(self)
+    }
+}
+
+impl IntoFuture for SendBuilder {
+    type Output = Result<i64>;
+    type IntoFuture = BoxFuture<'static, Result<i64>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(async move {
+            let mut request = self
+                .vk
+                .method("messages.send")
+                .param("peer_id", self.peer_id)
+                .param("random_id", self.random_id);
+            if let Some(message) = &self.message {
+                request = request.param("message", message);
+            }
+            if !self.attachments.is_empty() {
+                request = request.param("attachment", self.attachments.join(","));
+            }
+            request.send().await
+        })
+    }
+}
@@ -0,0 +1,7 @@
+//! Typed builders over the VK API, e.g. `ctx.messages().send()`.
+
+mod messages;
+mod upload;
+
+pub use messages::{MessagesApi, SendBuilder};
+pub use upload::Attachment;
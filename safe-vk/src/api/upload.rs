@@ -0,0 +1,118 @@
+//! The three-step upload dance (`*.getMessagesUploadServer` -> POST the
+//! file -> `*.save*`) behind `SendBuilder::attach_photo` and friends.
+
+use std::{fmt, path::Path};
+
+use serde::Deserialize;
+
+use crate::{reqwest_ext::VK, Error, Result};
+
+/// A VK attachment reference, e.g. `photo123_456`, ready to pass as
+/// `messages.send`'s `attachment` parameter.
+#[derive(Debug, Clone)]
+pub struct Attachment(String);
+
+impl fmt::Display for Attachment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Deserialize)]
+struct UploadServer {
+    upload_url: String,
+}
+
+#[derive(Deserialize)]
+struct PhotoUpload {
+    server: i64,
+    photo: String,
+    hash: String,
+}
+
+#[derive(Deserialize)]
+struct SavedPhoto {
+    owner_id: i64,
+    id: i64,
+}
+
+#[derive(Deserialize)]
+struct DocUpload {
+    file: String,
+}
+
+/// `docs.save`'s response nests the saved object under a key matching the
+/// upload's `type` (`doc`, `audio_message`, `graffiti`, ...), not always
+/// `doc`, so this holds the raw object and [`SavedDoc::into_inner`] picks
+/// the field matching the type that was actually uploaded.
+#[derive(Deserialize)]
+struct SavedDoc(serde_json::Value);
+
+#[derive(Deserialize)]
+struct SavedDocInner {
+    #[serde(rename = "type")]
+    kind: String,
+    owner_id: i64,
+    id: i64,
+}
+
+impl SavedDoc {
+    /// Extracts the `SavedDocInner` nested under `doctype` (the same
+    /// `type` passed to `docs.getMessagesUploadServer`/`docs.save`).
+    fn into_inner(self, doctype: &str) -> Result<SavedDocInner> {
+        let object = self
+            .0
+            .get(doctype)
+            .ok_or_else(|| Error::Json(format!("docs.save response missing \"{doctype}\" field")))?;
+        serde_json::from_value(object.clone()).map_err(|e| Error::Json(e.to_string()))
+    }
+}
+
+/// Uploads `path` as a photo attached to messages sent to `peer_id`.
+pub(crate) async fn photo(vk: &VK, peer_id: i64, path: &Path) -> Result<Attachment> {
+    let server: UploadServer = vk
+        .method("photos.getMessagesUploadServer")
+        .param("peer_id", peer_id)
+        .send()
+        .await?;
+
+    let uploaded: PhotoUpload = vk.raw(server.upload_url).send_multipart("photo", path).await?;
+
+    let saved: Vec<SavedPhoto> = vk
+        .method("photos.saveMessagesPhoto")
+        .param("server", uploaded.server)
+        .param("photo", uploaded.photo)
+        .param("hash", uploaded.hash)
+        .send()
+        .await?;
+    let saved = saved
+        .into_iter()
+        .next()
+        .ok_or_else(|| Error::Json("photos.saveMessagesPhoto returned no photos".to_owned()))?;
+    Ok(Attachment(format!("photo{}_{}", saved.owner_id, saved.id)))
+}
+
+/// Uploads `path` as a document attached to messages sent to `peer_id`.
+pub(crate) async fn document(vk: &VK, peer_id: i64, path: &Path) -> Result<Attachment> {
+    upload_doc(vk, peer_id, path, "doc").await
+}
+
+/// Uploads `path` as a voice message attached to messages sent to `peer_id`.
+pub(crate) async fn voice(vk: &VK, peer_id: i64, path: &Path) -> Result<Attachment> {
+    upload_doc(vk, peer_id, path, "audio_message").await
+}
+
+async fn upload_doc(vk: &VK, peer_id: i64, path: &Path, doctype: &str) -> Result<Attachment> {
+    let server: UploadServer = vk
+        .method("docs.getMessagesUploadServer")
+        .param("peer_id", peer_id)
+        .param("type", doctype)
+        .send()
+        .await?;
+
+    let uploaded: DocUpload = vk.raw(server.upload_url).send_multipart("file", path).await?;
+
+    let saved: SavedDoc = vk.method("docs.save").param("file", uploaded.file).send().await?;
+    let saved = saved.into_inner(doctype)?;
+    Ok(Attachment(format!("{}{}_{}", saved.kind, saved.owner_id, saved.id)))
+}
@@ -0,0 +1,122 @@
+//! Support code for `#[derive(Command)]` (see `safe_vk_macros`).
+//!
+//! The derive only generates field-binding glue; tokenizing the message
+//! text and pulling `--flag value` pairs out of it lives here so every
+//! generated `FromUpdate` impl shares the same parsing rules.
+
+/// Implemented by types generated via `#[derive(Command)]`.
+///
+/// `COMMAND` is the leading keyword the message must start with (e.g.
+/// `"/weather"`) and `usage()` renders the auto-generated usage string used
+/// when parsing the rest of the message fails. Left state-agnostic (unlike
+/// [`FromUpdate`](super::FromUpdate)) since the command keyword and usage
+/// string never depend on the application state.
+pub trait TypedCommand {
+    /// The command keyword this type is parsed from, including the slash.
+    const COMMAND: &'static str;
+
+    /// A human-readable usage string, e.g. `Usage: /weather <city> [--units metric]`.
+    fn usage() -> &'static str;
+}
+
+/// Splits `text` into whitespace-separated tokens, treating a
+/// double-quoted group (`"like this"`) as a single token with the quotes
+/// stripped.
+pub fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        let mut token = String::new();
+        if c == '"' {
+            chars.next();
+            for c in chars.by_ref() {
+                if c == '"' {
+                    break;
+                }
+                token.push(c);
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                token.push(c);
+                chars.next();
+            }
+        }
+        tokens.push(token);
+    }
+
+    tokens
+}
+
+/// Removes `--flag <value>` from `tokens` (in place) and returns `value`,
+/// if the flag was present. Used by fields annotated `#[arg(flag = "...")]`.
+pub fn take_flag(tokens: &mut Vec<String>, flag: &str) -> Option<String> {
+    let pos = tokens.iter().position(|t| t == flag)?;
+    if pos + 1 >= tokens.len() {
+        tokens.remove(pos);
+        return None;
+    }
+    tokens.remove(pos);
+    Some(tokens.remove(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenize_splits_on_whitespace() {
+        assert_eq!(tokenize("/weather paris --units metric"), vec!["/weather", "paris", "--units", "metric"]);
+    }
+
+    #[test]
+    fn tokenize_collapses_repeated_whitespace() {
+        assert_eq!(tokenize("  a   b\tc\n"), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn tokenize_keeps_quoted_group_as_one_token() {
+        assert_eq!(tokenize(r#"/weather "New York" --units metric"#), vec!["/weather", "New York", "--units", "metric"]);
+    }
+
+    #[test]
+    fn tokenize_empty_text_is_no_tokens() {
+        assert_eq!(tokenize("   "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn take_flag_removes_flag_and_its_value() {
+        let mut tokens = vec!["paris".to_owned(), "--units".to_owned(), "metric".to_owned()];
+        assert_eq!(take_flag(&mut tokens, "--units"), Some("metric".to_owned()));
+        assert_eq!(tokens, vec!["paris".to_owned()]);
+    }
+
+    #[test]
+    fn take_flag_missing_flag_leaves_tokens_untouched() {
+        let mut tokens = vec!["paris".to_owned()];
+        assert_eq!(take_flag(&mut tokens, "--units"), None);
+        assert_eq!(tokens, vec!["paris".to_owned()]);
+    }
+
+    #[test]
+    fn take_flag_with_no_value_removes_flag_and_returns_none() {
+        let mut tokens = vec!["paris".to_owned(), "--units".to_owned()];
+        assert_eq!(take_flag(&mut tokens, "--units"), None);
+        assert_eq!(tokens, vec!["paris".to_owned()]);
+    }
+
+    #[test]
+    fn take_flag_only_removes_first_occurrence() {
+        let mut tokens = vec!["--x".to_owned(), "1".to_owned(), "--x".to_owned(), "2".to_owned()];
+        assert_eq!(take_flag(&mut tokens, "--x"), Some("1".to_owned()));
+        assert_eq!(tokens, vec!["--x".to_owned(), "2".to_owned()]);
+    }
+}
@@ -0,0 +1,69 @@
+//! Extractors pull typed values out of an incoming update, the bot's VK
+//! client, and (once
+//! [`SafeVk::with_state`](crate::routing::SafeVk::with_state) is used) the
+//! application state.
+//!
+//! A handler's argument list is just a tuple of types implementing
+//! [`FromUpdate`]; the router builds each one in turn before invoking the
+//! handler, the same way axum builds a handler's arguments from
+//! `FromRequestParts`.
+
+use std::ops::Deref;
+
+use crate::{api::MessagesApi, reqwest_ext::VK, responses::Message, Error};
+
+mod command;
+mod state;
+
+pub use command::{take_flag, tokenize, TypedCommand};
+pub use state::State;
+
+/// Implemented by anything that can be built from an incoming [`Message`],
+/// the bot's VK client, and the application state `S`.
+///
+/// Extractors that don't care about `vk` or `S` (like the derived
+/// `#[derive(Command)]` types) can stay generic over them; only [`Ctx`]
+/// and [`State`] actually use them.
+pub trait FromUpdate<S = ()>: Sized {
+    /// Build `Self` from the raw update, the VK client, and the state, or
+    /// fail with a structured [`Error`].
+    fn from_update(update: &Message, vk: &VK, state: &S) -> Result<Self, Error>;
+}
+
+/// The raw update context handed to a handler, plus the bot's VK client so
+/// a handler can reply without an extra `State<VK>`.
+///
+/// `Ctx<Message>` is the extractor every handler starts with; wrapping the
+/// update keeps the door open for `Ctx<T>` over other update kinds later on.
+pub struct Ctx<T> {
+    value: T,
+    vk: VK,
+}
+
+impl<T> Deref for Ctx<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl Ctx<Message> {
+    /// Entry point for `messages.*` calls, defaulting to the peer this
+    /// update came from.
+    pub fn messages(&self) -> MessagesApi {
+        MessagesApi::new(self.vk.clone(), self.value.peer_id)
+    }
+}
+
+impl<S> FromUpdate<S> for Ctx<Message> {
+    fn from_update(update: &Message, vk: &VK, _state: &S) -> Result<Self, Error> {
+        Ok(Ctx { value: update.clone(), vk: vk.clone() })
+    }
+}
+
+impl<S> FromUpdate<S> for Message {
+    fn from_update(update: &Message, _vk: &VK, _state: &S) -> Result<Self, Error> {
+        Ok(update.clone())
+    }
+}
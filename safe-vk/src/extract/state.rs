@@ -0,0 +1,31 @@
+use std::ops::Deref;
+
+use super::FromUpdate;
+use crate::{reqwest_ext::VK, responses::Message, Error};
+
+/// Pulls the application state configured via
+/// [`SafeVk::with_state`](crate::routing::SafeVk::with_state) into a
+/// handler, mirroring axum's `State<S>`.
+///
+/// ```ignore
+/// async fn h(ctx: Ctx<Message>, State(db): State<Pool>) -> Result<()> { .. }
+/// ```
+#[derive(Debug, Clone)]
+pub struct State<S>(pub S);
+
+impl<S> Deref for State<S> {
+    type Target = S;
+
+    fn deref(&self) -> &S {
+        &self.0
+    }
+}
+
+impl<S> FromUpdate<S> for State<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    fn from_update(_update: &Message, _vk: &VK, state: &S) -> Result<Self, Error> {
+        Ok(State(state.clone()))
+    }
+}
@@ -0,0 +1,47 @@
+//! The [`Handler`] trait implemented by any `async fn` whose arguments are
+//! all extractors (see [`extract`](crate::extract)).
+
+use std::{future::Future, pin::Pin};
+
+use crate::{extract::FromUpdate, reqwest_ext::VK, responses::Message, Result};
+
+pub(crate) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A route target: an async function taking extractors and returning a
+/// [`Result`](crate::Result).
+///
+/// `T` is the tuple of extractor types the function takes, which lets a
+/// single `F` implement `Handler` once per argument count via the
+/// `impl_handler!` expansions below. `S` is the application state threaded
+/// in from [`SafeVk::with_state`](crate::routing::SafeVk::with_state), so a
+/// `State<S>` argument (see [`extract::State`](crate::extract::State)) can
+/// be built alongside the other extractors.
+pub trait Handler<T, S>: Clone + Send + Sized + 'static {
+    /// Build the handler's arguments from `update`, `vk` and `state`, then
+    /// run it.
+    fn call(self, update: Message, vk: VK, state: S) -> BoxFuture<'static, Result<()>>;
+}
+
+macro_rules! impl_handler {
+    ($($ty:ident),*) => {
+        #[allow(non_snake_case, unused_parens)]
+        impl<F, Fut, S, $($ty,)*> Handler<($($ty,)*), S> for F
+        where
+            F: FnOnce($($ty),*) -> Fut + Clone + Send + 'static,
+            Fut: Future<Output = Result<()>> + Send + 'static,
+            S: Clone + Send + Sync + 'static,
+            $($ty: FromUpdate<S> + Send,)*
+        {
+            fn call(self, update: Message, vk: VK, state: S) -> BoxFuture<'static, Result<()>> {
+                Box::pin(async move {
+                    $(let $ty = $ty::from_update(&update, &vk, &state)?;)*
+                    self($($ty),*).await
+                })
+            }
+        }
+    };
+}
+
+impl_handler!(T1);
+impl_handler!(T1, T2);
+impl_handler!(T1, T2, T3);
@@ -82,20 +82,32 @@
 #![cfg_attr(test, allow(clippy::float_cmp))]
 #![cfg_attr(not(test), warn(clippy::print_stdout, clippy::dbg_macro))]
 
-#[macro_use]
-pub(crate) mod macros;
+mod reqwest_ext;
 
+pub mod api;
 pub mod extract;
 pub mod handler;
 pub mod responses;
 pub mod routing;
+pub mod service;
+#[cfg(feature = "tokio")]
+pub mod start_polling;
+#[cfg(feature = "tokio")]
+pub mod webhook;
 pub use safe_vk_common::*;
 
+pub use self::reqwest_ext::{RequestBuilder, VERSION, VK, WAIT_TIME};
 pub use self::routing::SafeVk;
 
 //#[cfg(feature = "macros")]
 pub use safe_vk_macros::*;
 
+#[cfg(feature = "tokio")]
+pub use self::start_polling::start_polling;
+
+#[cfg(feature = "tokio")]
+pub use self::webhook::{start_webhook, WebhookConfig};
+
 #[cfg(feature = "tokio")]
 pub type Response<T> = Result<T>;
 
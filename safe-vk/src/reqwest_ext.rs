@@ -0,0 +1,188 @@
+//! A thin extension over [`reqwest`] for calling the VK API: builds the
+//! method URL, attaches the access token and API version, and unwraps VK's
+//! `{"response": ...}` / `{"error": ...}` envelope.
+
+use std::{path::Path, time::Duration};
+
+use serde::de::DeserializeOwned;
+
+use crate::{service::rate_limit::Limiter, Error, Result};
+
+/// The VK API version every request is pinned to.
+pub const VERSION: &str = "5.199";
+
+/// How long a Long Poll request waits for a new update before VK returns
+/// an empty response, per VK's recommended `wait` parameter.
+pub const WAIT_TIME: Duration = Duration::from_secs(25);
+
+/// The VK API error code for "Too many requests per second".
+const TOO_MANY_REQUESTS: i64 = 6;
+
+/// A thin wrapper over [`reqwest::Client`] bound to a single access token.
+/// Every outgoing call, whether a plain API method or a file upload, goes
+/// through a [`RequestBuilder`] built by [`VK::method`] or [`VK::raw`].
+#[derive(Clone)]
+pub struct VK {
+    client: reqwest::Client,
+    token: String,
+    limiters: Vec<Limiter>,
+}
+
+impl VK {
+    /// Creates a client bound to `token`, with no rate limiting.
+    pub fn new(token: impl Into<String>) -> Self {
+        Self { client: reqwest::Client::new(), token: token.into(), limiters: Vec::new() }
+    }
+
+    /// The access token this client was created with.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Returns a copy of this client that also acquires a token from
+    /// `limiter` before every outbound call, on top of any limiters already
+    /// attached. Used by [`RateLimit`](crate::service::RateLimit) to
+    /// implement [`SafeVk::layer`](crate::routing::SafeVk::layer): stacking
+    /// several `RateLimit` layers enforces every bucket, not just the last
+    /// one registered.
+    pub(crate) fn with_limiter(&self, limiter: Limiter) -> Self {
+        let mut limiters = self.limiters.clone();
+        limiters.push(limiter);
+        Self { limiters, ..self.clone() }
+    }
+
+    /// Starts building a call to `method` (e.g. `"messages.send"`), posted
+    /// to `https://api.vk.com/method/<method>`.
+    pub fn method(&self, method: impl Into<String>) -> RequestBuilder {
+        RequestBuilder::method(self.client.clone(), self.token.clone(), self.limiters.clone(), method)
+    }
+
+    /// Starts building a request to an arbitrary absolute URL instead of an
+    /// `api.vk.com` method — used for the Long Poll server URL VK hands
+    /// back from `groups.getLongPollServer`, and for the upload server URLs
+    /// from `*.getMessagesUploadServer` (where only
+    /// [`RequestBuilder::send_multipart`] makes sense on the result).
+    pub(crate) fn raw(&self, url: impl Into<String>) -> RequestBuilder {
+        RequestBuilder::raw(self.client.clone(), self.limiters.clone(), url)
+    }
+}
+
+#[derive(Clone)]
+enum Target {
+    Method(String),
+    Url(String),
+}
+
+/// Builds a single VK API call, form-encoding parameters added with
+/// [`param`](RequestBuilder::param) and parsing the JSON envelope VK wraps
+/// every response in.
+#[derive(Clone)]
+pub struct RequestBuilder {
+    client: reqwest::Client,
+    token: String,
+    target: Target,
+    params: Vec<(String, String)>,
+    limiters: Vec<Limiter>,
+}
+
+impl RequestBuilder {
+    pub(crate) fn method(
+        client: reqwest::Client,
+        token: String,
+        limiters: Vec<Limiter>,
+        method: impl Into<String>,
+    ) -> Self {
+        Self { client, token, target: Target::Method(method.into()), params: Vec::new(), limiters }
+    }
+
+    pub(crate) fn raw(client: reqwest::Client, limiters: Vec<Limiter>, url: impl Into<String>) -> Self {
+        Self { client, token: String::new(), target: Target::Url(url.into()), params: Vec::new(), limiters }
+    }
+
+    /// Adds a form parameter to the request.
+    pub fn param(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.params.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Sends the request and deserializes VK's `response` field into `T`.
+    pub async fn send<T: DeserializeOwned>(self) -> Result<T> {
+        let url = match &self.target {
+            Target::Method(method) => format!("https://api.vk.com/method/{method}"),
+            Target::Url(url) => url.clone(),
+        };
+
+        let mut params = self.params;
+        if let Target::Method(_) = &self.target {
+            params.push(("access_token".to_owned(), self.token));
+            params.push(("v".to_owned(), VERSION.to_owned()));
+        }
+
+        for limiter in &self.limiters {
+            limiter.acquire().await;
+        }
+
+        let response = self
+            .client
+            .post(url)
+            .form(&params)
+            .send()
+            .await
+            .map_err(|e| Error::Http(e.to_string()))?;
+
+        let value: serde_json::Value =
+            response.json().await.map_err(|e| Error::Json(e.to_string()))?;
+        if let Some(error) = value.get("error") {
+            let code = error.get("error_code").and_then(|c| c.as_i64()).unwrap_or(0);
+            let message = error
+                .get("error_msg")
+                .and_then(|m| m.as_str())
+                .unwrap_or("unknown error")
+                .to_owned();
+            if code == TOO_MANY_REQUESTS {
+                for limiter in &self.limiters {
+                    limiter.backoff();
+                }
+            }
+            return Err(Error::Vk { code, message });
+        }
+        crate::parse_response!(value, T).map_err(|e| Error::Json(e.to_string()))
+    }
+
+    /// Streams `path` as `multipart/form-data` under `field_name` to this
+    /// request's upload URL, for the VK upload-server endpoints that expect
+    /// a raw file POST rather than form-encoded parameters.
+    ///
+    /// Only valid on a [`RequestBuilder`] created via [`VK::raw`].
+    pub async fn send_multipart<T: DeserializeOwned>(
+        self,
+        field_name: &str,
+        path: &Path,
+    ) -> Result<T> {
+        let Target::Url(url) = self.target else {
+            return Err(Error::Http("send_multipart requires an upload URL".to_owned()));
+        };
+
+        for limiter in &self.limiters {
+            limiter.acquire().await;
+        }
+
+        let bytes = tokio::fs::read(path).await.map_err(|e| Error::Http(e.to_string()))?;
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("file")
+            .to_owned();
+        let part = reqwest::multipart::Part::bytes(bytes).file_name(file_name);
+        let form = reqwest::multipart::Form::new().part(field_name.to_owned(), part);
+
+        let response = self
+            .client
+            .post(url)
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| Error::Http(e.to_string()))?;
+        response.json().await.map_err(|e| Error::Json(e.to_string()))
+    }
+}
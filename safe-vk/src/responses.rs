@@ -0,0 +1,14 @@
+//! Types deserialized from VK API responses and Long Poll / Callback API events.
+
+use serde::Deserialize;
+
+/// A single incoming message, as delivered by VK's `message_new` event.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Message {
+    pub id: i64,
+    pub peer_id: i64,
+    pub from_id: i64,
+    pub text: String,
+    #[serde(default)]
+    pub attachments: Vec<serde_json::Value>,
+}
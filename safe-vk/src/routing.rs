@@ -0,0 +1,242 @@
+//! Route registration: map a command keyword (or a typed extractor) to a
+//! handler, the way [`axum::Router`](https://docs.rs/axum) maps a path to one.
+//!
+//! Every [`command`](SafeVk::command)/[`typed_command`](SafeVk::typed_command)
+//! call also records a [`CommandInfo`] entry, borrowing the code-first
+//! introspection idea from `aide`: [`SafeVk::manifest`] lets the bot
+//! describe itself at runtime instead of that documentation drifting out
+//! of sync with the actual routes.
+
+use std::sync::Arc;
+
+use crate::{
+    extract::{Ctx, FromUpdate, TypedCommand},
+    handler::{BoxFuture, Handler},
+    reqwest_ext::VK,
+    responses::Message,
+    service::Layer,
+    Filter, Result,
+};
+
+type DynHandler<S> = Arc<dyn Fn(Message, VK, S) -> BoxFuture<'static, Result<()>> + Send + Sync>;
+
+struct Route<S> {
+    command: String,
+    filter: Filter,
+    handler: DynHandler<S>,
+    description: Option<String>,
+    usage: Option<String>,
+}
+
+// Derived `Clone` would require `S: Clone`, but `S` never actually appears
+// in a stored field (only inside the `DynHandler`'s function signature), so
+// clone by hand instead.
+impl<S> Clone for Route<S> {
+    fn clone(&self) -> Self {
+        Self {
+            command: self.command.clone(),
+            filter: self.filter,
+            handler: Arc::clone(&self.handler),
+            description: self.description.clone(),
+            usage: self.usage.clone(),
+        }
+    }
+}
+
+/// A registered command's metadata, as returned by [`SafeVk::manifest`].
+#[derive(Debug, Clone)]
+pub struct CommandInfo {
+    /// The command keyword (e.g. `/weather`).
+    pub name: String,
+    /// How strictly the message text must match [`name`](Self::name).
+    pub filter: Filter,
+    /// The human-readable summary passed to
+    /// [`describe`](SafeVk::describe), if any.
+    pub description: Option<String>,
+    /// The auto-generated usage string for a `typed_command`, if any.
+    pub usage: Option<String>,
+}
+
+/// The SafeVk router: a list of commands, each paired with a [`Filter`] and
+/// a handler.
+///
+/// `S` is application state shared across every handler; see
+/// [`with_state`](SafeVk::with_state). Routers that never call
+/// `with_state` stay `SafeVk<()>`, same as axum's `Router<()>`.
+pub struct SafeVk<S = ()> {
+    routes: Arc<Vec<Route<S>>>,
+    state: Arc<S>,
+    layers: Arc<Vec<Arc<dyn Layer>>>,
+}
+
+impl<S> Clone for SafeVk<S> {
+    fn clone(&self) -> Self {
+        Self {
+            routes: Arc::clone(&self.routes),
+            state: Arc::clone(&self.state),
+            layers: Arc::clone(&self.layers),
+        }
+    }
+}
+
+impl Default for SafeVk<()> {
+    fn default() -> Self {
+        Self { routes: Arc::new(Vec::new()), state: Arc::new(()), layers: Arc::new(Vec::new()) }
+    }
+}
+
+impl SafeVk<()> {
+    /// Creates a router with no routes registered and no shared state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: Clone + Send + Sync + 'static> SafeVk<S> {
+    /// Attaches application state (a database pool, an HTTP client, config,
+    /// ...) that handlers can pull out with the
+    /// [`State`](crate::extract::State) extractor.
+    ///
+    /// Mirrors axum: decide your state before you register routes that
+    /// depend on it. Unlike axum, `S` isn't fixed by the router's type
+    /// alone, so calling this after [`command`](Self::command)/
+    /// [`typed_command`](Self::typed_command) would silently drop those
+    /// routes instead of failing to compile — panics instead of doing that
+    /// quietly.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any route has already been registered on `self`.
+    pub fn with_state<S2>(self, state: S2) -> SafeVk<S2>
+    where
+        S2: Clone + Send + Sync + 'static,
+    {
+        assert!(
+            self.routes.is_empty(),
+            "SafeVk::with_state called after routes were already registered; \
+             call with_state before command/typed_command"
+        );
+        SafeVk { routes: Arc::new(Vec::new()), state: Arc::new(state), layers: self.layers }
+    }
+
+    /// Registers a middleware layer (e.g. [`RateLimit`](crate::service::RateLimit))
+    /// that wraps the VK client every handler's outbound calls go through.
+    /// Layers run in registration order, outermost first.
+    pub fn layer(mut self, layer: impl Layer + 'static) -> Self {
+        Arc::make_mut(&mut self.layers).push(Arc::new(layer));
+        self
+    }
+
+    /// Runs `vk` through every registered layer, outermost first. Called by
+    /// [`Service::new`](crate::service::Service::new) when a transport
+    /// starts the router.
+    pub(crate) fn build_vk(&self, vk: VK) -> VK {
+        self.layers.iter().fold(vk, |vk, layer| layer.wrap(vk))
+    }
+
+    /// Registers a plain-text command. `handler` receives `Ctx<Message>`
+    /// (and any other extractor implementing
+    /// [`FromUpdate`](crate::extract::FromUpdate)).
+    pub fn command<H, T>(self, command: impl Into<String>, handler: H, filter: Filter) -> Self
+    where
+        H: Handler<T, S> + Clone + Send + Sync + 'static,
+        T: 'static,
+    {
+        self.push_route(command.into(), filter, handler, None)
+    }
+
+    /// Registers a command whose arguments are parsed into `C` via
+    /// `#[derive(Command)]`. The command keyword and usage string both come
+    /// from `C`'s [`TypedCommand`] impl, so they stay in lockstep with the
+    /// struct definition.
+    pub fn typed_command<C, H>(self, handler: H, filter: Filter) -> Self
+    where
+        C: TypedCommand + FromUpdate<S> + Send + 'static,
+        H: Handler<(C,), S> + Clone + Send + Sync + 'static,
+    {
+        self.push_route(C::COMMAND.to_owned(), filter, handler, Some(C::usage().to_owned()))
+    }
+
+    /// Attaches a human-readable description to the command registered by
+    /// the previous [`command`](Self::command)/[`typed_command`](Self::typed_command)
+    /// call, surfaced by [`manifest`](Self::manifest) and the built-in
+    /// [`help_command`](Self::help_command).
+    pub fn describe(mut self, description: impl Into<String>) -> Self {
+        if let Some(route) = Arc::make_mut(&mut self.routes).last_mut() {
+            route.description = Some(description.into());
+        }
+        self
+    }
+
+    /// Registers a built-in handler for `command` that replies with every
+    /// registered command's name, usage, and description, so documentation
+    /// lives in the source and never drifts from the actual routes. Only
+    /// sees commands registered before this call.
+    pub fn help_command(self, command: impl Into<String>) -> Self {
+        let text = render_manifest(&self.manifest());
+        self.command(
+            command,
+            move |ctx: Ctx<Message>| {
+                let text = text.clone();
+                async move {
+                    ctx.messages().send().message(text).await?;
+                    Ok(())
+                }
+            },
+            Filter::Strict,
+        )
+    }
+
+    fn push_route<H, T>(
+        mut self,
+        command: String,
+        filter: Filter,
+        handler: H,
+        usage: Option<String>,
+    ) -> Self
+    where
+        H: Handler<T, S> + Clone + Send + Sync + 'static,
+        T: 'static,
+    {
+        let handler: DynHandler<S> =
+            Arc::new(move |update, vk, state| handler.clone().call(update, vk, state));
+        Arc::make_mut(&mut self.routes)
+            .push(Route { command, filter, handler, description: None, usage });
+        self
+    }
+
+    /// Dispatches `update` to the first route whose filter matches, if any,
+    /// cloning `vk` and the shared state into that handler's extractor set.
+    pub async fn dispatch(&self, update: Message, vk: &VK) -> Option<Result<()>> {
+        let route = self
+            .routes
+            .iter()
+            .find(|route| route.filter.matches(&route.command, &update.text))?;
+        Some((route.handler)(update, vk.clone(), (*self.state).clone()).await)
+    }
+
+    /// Returns every registered command's metadata, in registration order.
+    pub fn manifest(&self) -> Vec<CommandInfo> {
+        self.routes
+            .iter()
+            .map(|route| CommandInfo {
+                name: route.command.clone(),
+                filter: route.filter,
+                description: route.description.clone(),
+                usage: route.usage.clone(),
+            })
+            .collect()
+    }
+}
+
+fn render_manifest(manifest: &[CommandInfo]) -> String {
+    let mut lines = vec!["Available commands:".to_owned()];
+    for command in manifest {
+        let mut line = command.usage.clone().unwrap_or_else(|| command.name.clone());
+        if let Some(description) = &command.description {
+            line.push_str(&format!(" — {description}"));
+        }
+        lines.push(line);
+    }
+    lines.join("\n")
+}
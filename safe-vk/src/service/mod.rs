@@ -0,0 +1,57 @@
+//! The service that actually dispatches updates to a [`SafeVk`] router's
+//! routes. Long polling and the Callback API both end up calling this
+//! instead of touching `SafeVk::dispatch` directly, so they share one spot
+//! to add cross-cutting behavior (layers) to the VK client handlers reply
+//! through.
+
+pub(crate) mod rate_limit;
+
+pub use rate_limit::RateLimit;
+
+use crate::{api::MessagesApi, reqwest_ext::VK, responses::Message, routing::SafeVk, Error, Result};
+
+/// A tower-style middleware that wraps the VK client every handler's
+/// outbound calls go through, the way `tower::Layer` wraps a `Service`.
+///
+/// Registered on a router with [`SafeVk::layer`](crate::routing::SafeVk::layer).
+pub trait Layer: Send + Sync {
+    /// Wraps `vk`, returning the client that actually gets handed to
+    /// handlers and their extractors.
+    fn wrap(&self, vk: VK) -> VK;
+}
+
+/// A thin, cloneable wrapper pairing a [`SafeVk<S>`] router with the VK
+/// client handlers reply through.
+#[derive(Clone)]
+pub struct Service<S = ()> {
+    router: SafeVk<S>,
+    vk: VK,
+}
+
+impl<S: Clone + Send + Sync + 'static> Service<S> {
+    /// Wraps `router` so it can be driven by a transport, replying via `vk`
+    /// after running it through every layer `router` was built with.
+    pub fn new(router: SafeVk<S>, vk: VK) -> Self {
+        let vk = router.build_vk(vk);
+        Self { router, vk }
+    }
+
+    /// Runs `update` through the router's routes, returning `Ok(None)` if
+    /// none of them matched.
+    ///
+    /// If an extractor fails with [`Error::Extraction`] (e.g. a
+    /// `#[derive(Command)]` argument that didn't parse), the usage string
+    /// it carries is sent back to the peer before the error is returned, so
+    /// the caller never has to unpack it to reply.
+    pub async fn call(&self, update: Message) -> Result<Option<()>> {
+        let peer_id = update.peer_id;
+        match self.router.dispatch(update, &self.vk).await {
+            Some(Err(Error::Extraction(usage))) => {
+                MessagesApi::new(self.vk.clone(), peer_id).send().message(usage.clone()).await?;
+                Err(Error::Extraction(usage))
+            }
+            Some(result) => result.map(Some),
+            None => Ok(None),
+        }
+    }
+}
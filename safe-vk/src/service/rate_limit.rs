@@ -0,0 +1,138 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::Layer;
+use crate::reqwest_ext::VK;
+
+/// Bounds outbound VK API calls to VK's per-token quotas (roughly 3/s for
+/// user tokens, 20/s for group tokens), which `WAIT_TIME` alone does
+/// nothing to enforce. A token bucket is shared across every concurrent
+/// handler, since the quota is per access token, not per request.
+pub struct RateLimit {
+    capacity: u32,
+    rate: f64,
+}
+
+impl RateLimit {
+    /// `n` tokens refill per second; the bucket also holds at most `n`
+    /// tokens, so a burst can use up to a second's worth of quota at once.
+    pub fn per_second(n: u32) -> Self {
+        Self { capacity: n, rate: n as f64 }
+    }
+}
+
+impl Layer for RateLimit {
+    fn wrap(&self, vk: VK) -> VK {
+        vk.with_limiter(Limiter::new(self.capacity, self.rate))
+    }
+}
+
+struct Bucket {
+    capacity: f64,
+    tokens: f64,
+    rate: f64,
+    last_refill: Instant,
+}
+
+impl Bucket {
+    fn new(capacity: u32, rate: f64) -> Self {
+        Self { capacity: capacity as f64, tokens: capacity as f64, rate, last_refill: Instant::now() }
+    }
+
+    fn refill(&mut self) {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = Instant::now();
+    }
+}
+
+/// A shared, cloneable handle onto a single token bucket.
+#[derive(Clone)]
+pub(crate) struct Limiter(Arc<Mutex<Bucket>>);
+
+impl Limiter {
+    fn new(capacity: u32, rate: f64) -> Self {
+        Self(Arc::new(Mutex::new(Bucket::new(capacity, rate))))
+    }
+
+    /// Waits, if necessary, until a token is available, then takes one.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.0.lock().expect("rate limit bucket poisoned");
+                bucket.refill();
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    None
+                } else {
+                    Some(Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate))
+                }
+            };
+            match wait {
+                None => return,
+                Some(duration) => tokio::time::sleep(duration).await,
+            }
+        }
+    }
+
+    /// Called after VK answers with error code 6 ("Too many requests per
+    /// second"): drains the bucket so the next `acquire` backs off for a
+    /// full refill interval instead of immediately retrying into the same
+    /// error.
+    pub(crate) fn backoff(&self) {
+        let mut bucket = self.0.lock().expect("rate limit bucket poisoned");
+        bucket.tokens = 0.0;
+        bucket.last_refill = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_starts_full() {
+        let bucket = Bucket::new(3, 3.0);
+        assert_eq!(bucket.tokens, 3.0);
+    }
+
+    #[test]
+    fn bucket_refill_is_capped_at_capacity() {
+        let mut bucket = Bucket::new(3, 3.0);
+        bucket.tokens = 0.0;
+        bucket.last_refill = Instant::now() - Duration::from_secs(10);
+        bucket.refill();
+        assert_eq!(bucket.tokens, 3.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_does_not_wait_while_tokens_remain() {
+        let limiter = Limiter::new(2, 2.0);
+        let start = tokio::time::Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(tokio::time::Instant::now(), start);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn acquire_waits_for_a_refill_once_the_bucket_is_empty() {
+        let limiter = Limiter::new(1, 1.0);
+        limiter.acquire().await;
+
+        let start = tokio::time::Instant::now();
+        limiter.acquire().await;
+        assert!(tokio::time::Instant::now() - start >= Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_drains_the_bucket_so_the_next_acquire_waits_a_full_refill() {
+        let limiter = Limiter::new(5, 5.0);
+        limiter.backoff();
+
+        let start = tokio::time::Instant::now();
+        limiter.acquire().await;
+        assert!(tokio::time::Instant::now() - start >= Duration::from_secs_f64(1.0 / 5.0));
+    }
+}
@@ -0,0 +1,76 @@
+//! Long Poll transport: the default way to run a [`SafeVk`] router without
+//! exposing a public URL. See [`webhook::start_webhook`](crate::webhook::start_webhook)
+//! for the Callback API alternative.
+
+use serde::Deserialize;
+
+use crate::{reqwest_ext::VK, responses::Message, routing::SafeVk, service::Service, Result, WAIT_TIME};
+
+#[derive(Deserialize)]
+struct LongPollServer {
+    server: String,
+    key: String,
+    ts: String,
+}
+
+#[derive(Deserialize)]
+struct LongPollUpdate {
+    #[serde(default)]
+    ts: Option<String>,
+    #[serde(default)]
+    updates: Vec<serde_json::Value>,
+    #[serde(default)]
+    failed: Option<i64>,
+}
+
+/// Starts a Long Poll loop against `groups.getLongPollServer`, dispatching
+/// every `message_new` update through `router`, replying via a VK client
+/// built from `token`.
+pub async fn start_polling<S>(token: impl Into<String>, router: SafeVk<S>) -> Result<()>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let vk = VK::new(token);
+    let service = Service::new(router, vk.clone());
+
+    let mut server: LongPollServer = vk.method("groups.getLongPollServer").send().await?;
+
+    loop {
+        let url = format!(
+            "{}?act=a_check&key={}&ts={}&wait={}",
+            server.server,
+            server.key,
+            server.ts,
+            WAIT_TIME.as_secs()
+        );
+        let update: LongPollUpdate = vk.raw(url).send().await?;
+
+        if update.failed.is_some() {
+            // The Long Poll key/ts pair expired or was invalidated; fetch a
+            // fresh server and retry instead of tearing down the bot.
+            server = vk.method("groups.getLongPollServer").send().await?;
+            continue;
+        }
+
+        if let Some(ts) = update.ts {
+            server.ts = ts;
+        }
+
+        for event in update.updates {
+            if event.get("type").and_then(|t| t.as_str()) != Some("message_new") {
+                continue;
+            }
+            let Some(object) = event.get("object").and_then(|o| o.get("message")) else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_value::<Message>(object.clone()) else {
+                continue;
+            };
+
+            let service = service.clone();
+            tokio::spawn(async move {
+                let _ = service.call(message).await;
+            });
+        }
+    }
+}
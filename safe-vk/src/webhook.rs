@@ -0,0 +1,174 @@
+//! Callback API (webhook) transport: an alternative to
+//! [`start_polling`](crate::start_polling) for bots that run behind a
+//! public URL instead of long-polling VK.
+//!
+//! ```ignore
+//! let addr = "0.0.0.0:8080".parse().unwrap();
+//! let config = WebhookConfig::new(addr, "a1b2c3d4").secret("my-secret-key");
+//! safe_vk::start_webhook("my super secret token", config, bot).await.unwrap();
+//! ```
+//!
+//! Both transports feed into the same [`routing::SafeVk`](crate::routing::SafeVk)
+//! dispatch used by polling, so handlers need no changes to run behind
+//! either one.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{extract::State as AxumState, routing::post, Json, Router};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{reqwest_ext::VK, responses::Message, routing::SafeVk, service::Service, Error, Result};
+
+/// Configuration for [`start_webhook`].
+#[derive(Clone)]
+pub struct WebhookConfig {
+    addr: SocketAddr,
+    confirmation: String,
+    secret: Option<String>,
+}
+
+impl WebhookConfig {
+    /// `addr` is where the HTTP server listens; `confirmation` is the
+    /// string VK expects back for the `confirmation` event, shown on the
+    /// community's Callback API settings page.
+    pub fn new(addr: SocketAddr, confirmation: impl Into<String>) -> Self {
+        Self { addr, confirmation: confirmation.into(), secret: None }
+    }
+
+    /// Requires every incoming event to carry this secret key, silently
+    /// dropping ones that don't.
+    pub fn secret(mut self, secret: impl Into<String>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+}
+
+/// The subset of a Callback API event's fields every event type carries,
+/// plus the raw `object` payload for event-specific deserialization.
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    event_id: String,
+    #[serde(default)]
+    secret: Option<String>,
+    #[serde(default)]
+    object: Value,
+}
+
+/// The number of `event_id`s [`SeenEvents`] remembers before evicting the
+/// oldest one. VK only redelivers an event a handful of times within a few
+/// seconds of it firing, so this comfortably covers retries without the
+/// set growing for the life of the process.
+const SEEN_EVENTS_CAPACITY: usize = 10_000;
+
+/// A bounded, FIFO-evicting de-duplication set for Callback API
+/// `event_id`s: a plain `HashSet` would grow for as long as the webhook
+/// process runs, leaking memory for every distinct event it ever saw.
+struct SeenEvents {
+    set: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl SeenEvents {
+    fn new() -> Self {
+        Self { set: HashSet::new(), order: VecDeque::new() }
+    }
+
+    /// Returns `true` if `event_id` was newly inserted (i.e. hasn't been
+    /// seen recently), evicting the oldest entry first if at capacity.
+    fn insert(&mut self, event_id: String) -> bool {
+        if !self.set.insert(event_id.clone()) {
+            return false;
+        }
+        self.order.push_back(event_id);
+        if self.order.len() > SEEN_EVENTS_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+struct WebhookState<S> {
+    service: Service<S>,
+    confirmation: String,
+    secret: Option<String>,
+    seen_events: Mutex<SeenEvents>,
+}
+
+/// Starts an HTTP server implementing VK's Callback API: answers the
+/// initial confirmation challenge, validates the optional secret key,
+/// de-duplicates retried events by `event_id`, and dispatches every
+/// `message_new` event through `router`, replying via a VK client built
+/// from `token`.
+pub async fn start_webhook<S>(
+    token: impl Into<String>,
+    config: WebhookConfig,
+    router: SafeVk<S>,
+) -> Result<()>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let addr = config.addr;
+    let state = Arc::new(WebhookState {
+        service: Service::new(router, VK::new(token)),
+        confirmation: config.confirmation,
+        secret: config.secret,
+        seen_events: Mutex::new(SeenEvents::new()),
+    });
+
+    let app = Router::new().route("/", post(handle_event)).with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| Error::Http(e.to_string()))?;
+    axum::serve(listener, app)
+        .await
+        .map_err(|e| Error::Http(e.to_string()))
+}
+
+async fn handle_event<S>(
+    AxumState(state): AxumState<Arc<WebhookState<S>>>,
+    Json(event): Json<Event>,
+) -> String
+where
+    S: Clone + Send + Sync + 'static,
+{
+    if let Some(expected) = &state.secret {
+        if event.secret.as_deref() != Some(expected.as_str()) {
+            return "ok".to_owned();
+        }
+    }
+
+    if event.kind == "confirmation" {
+        return state.confirmation.clone();
+    }
+
+    if !event.event_id.is_empty() {
+        let mut seen = state.seen_events.lock().expect("seen_events poisoned");
+        if !seen.insert(event.event_id.clone()) {
+            return "ok".to_owned();
+        }
+    }
+
+    if event.kind == "message_new" {
+        if let Ok(message) = serde_json::from_value::<Message>(event.object) {
+            let service = state.service.clone();
+            // VK redelivers an event it doesn't get an "ok" for quickly, so
+            // the handler runs in the background and we respond right away.
+            tokio::spawn(async move {
+                let _ = service.call(message).await;
+            });
+        }
+    }
+
+    "ok".to_owned()
+}